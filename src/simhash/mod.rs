@@ -0,0 +1,175 @@
+mod rademacher;
+mod sim_hash;
+mod superbit;
+pub mod sim_hasher;
+
+pub use rademacher::RademacherSource;
+pub use sim_hash::SimHash;
+pub use superbit::{StateError, SuperBitSimHash};
+pub use sim_hasher::{
+    AHash64, AHash128, FxHash64, FxHash128, SimSipHasher64, SimSipHasher64C, SimSipHasher128,
+    SimSipHasher128C, Xxh3Hasher64, Xxh3Hasher128,
+};
+
+use std::ops::{BitAnd, BitOr, BitOrAssign, Shl, Shr, ShrAssign};
+
+/// Anything that can act as the fixed-width bit-vector a [`SimHash`] or
+/// [`SuperBitSimHash`] signature is built out of.
+///
+/// Implemented for the native integer types (`u64`, `u128`) as well as
+/// [`BitArray`] for signatures wider than 128 bits.
+pub trait SimHashBits:
+    Copy
+    + PartialEq
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + BitAnd<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+    + ShrAssign<usize>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    /// Number of bits that differ between `self` and `other`.
+    fn hamming_distance(&self, other: &Self) -> usize;
+}
+
+macro_rules! impl_sim_hash_bits_for_uint {
+    ($($t:ty),+) => {
+        $(
+            impl SimHashBits for $t {
+                #[inline]
+                fn zero() -> Self { 0 }
+
+                #[inline]
+                fn one() -> Self { 1 }
+
+                #[inline]
+                fn hamming_distance(&self, other: &Self) -> usize {
+                    (self ^ other).count_ones() as usize
+                }
+            }
+        )+
+    };
+}
+
+impl_sim_hash_bits_for_uint!(u64, u128);
+
+/// A fixed-width bit vector backed by `N` `u64` words, for SimHash
+/// signatures wider than 128 bits (e.g. `BitArray<16>` for a 1024-bit
+/// signature).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BitArray<const N: usize>([u64; N]);
+
+impl<const N: usize> BitArray<N> {
+    pub const BITS: usize = N * 64;
+}
+
+impl<const N: usize> BitOr for BitArray<N> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+impl<const N: usize> BitOrAssign for BitArray<N> {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *a |= b;
+        }
+    }
+}
+
+impl<const N: usize> BitAnd for BitArray<N> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: Self) -> Self {
+        for (a, b) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *a &= b;
+        }
+        self
+    }
+}
+
+impl<const N: usize> Shl<usize> for BitArray<N> {
+    type Output = Self;
+
+    fn shl(self, amount: usize) -> Self {
+        let mut out = [0u64; N];
+        let word_shift = amount / 64;
+        let bit_shift = amount % 64;
+        for i in (0..N).rev() {
+            if i < word_shift {
+                continue;
+            }
+            let src = i - word_shift;
+            let mut word = self.0[src] << bit_shift;
+            if bit_shift > 0 && src > 0 {
+                word |= self.0[src - 1] >> (64 - bit_shift);
+            }
+            out[i] = word;
+        }
+        BitArray(out)
+    }
+}
+
+impl<const N: usize> Shr<usize> for BitArray<N> {
+    type Output = Self;
+
+    fn shr(mut self, amount: usize) -> Self {
+        self >>= amount;
+        self
+    }
+}
+
+impl<const N: usize> ShrAssign<usize> for BitArray<N> {
+    fn shr_assign(&mut self, amount: usize) {
+        let mut out = [0u64; N];
+        let word_shift = amount / 64;
+        let bit_shift = amount % 64;
+        for (i, out_word) in out.iter_mut().enumerate() {
+            let src = i + word_shift;
+            if src >= N {
+                continue;
+            }
+            let mut word = self.0[src] >> bit_shift;
+            if bit_shift > 0 && src + 1 < N {
+                word |= self.0[src + 1] << (64 - bit_shift);
+            }
+            *out_word = word;
+        }
+        self.0 = out;
+    }
+}
+
+impl<const N: usize> SimHashBits for BitArray<N> {
+    #[inline]
+    fn zero() -> Self {
+        BitArray([0u64; N])
+    }
+
+    #[inline]
+    fn one() -> Self {
+        let mut words = [0u64; N];
+        words[0] = 1;
+        BitArray(words)
+    }
+
+    #[inline]
+    fn hamming_distance(&self, other: &Self) -> usize {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(a, b)| (a ^ b).count_ones() as usize)
+            .sum()
+    }
+}
+
+/// Alias for [`SimHash`]; both names refer to the same type.
+pub type FastSimHash<H, S, const L: usize> = SimHash<H, S, L>;
@@ -0,0 +1,107 @@
+use std::hash::Hasher;
+
+use super::sim_hasher::SipStateC;
+
+/// A decorrelated `{+1,-1}` (Rademacher) sign stream, seeded from a
+/// hashable value rather than grown from a SplitMix64-style counter.
+///
+/// [`SuperBitSimHash::create_signature_weighted`](super::SuperBitSimHash::create_signature_weighted)
+/// needs one such stream per `(item, block)` pair. Seeding a keyed
+/// SipHash-1-3 keystream with the item's base hash and block index avoids
+/// the correlation a plain `SplitMix64(seed ^ base ^ (block << 32))` can
+/// have across adjacent blocks, since those only differ in a constant.
+pub struct RademacherSource {
+    state: SipStateC<1, 3>,
+    counter: u64,
+    bit_buf: u64,
+    bits_left: u32,
+}
+
+impl RademacherSource {
+    /// Seeds a keystream from `(k0, k1)` plus the item's base hash and the
+    /// block index it's being drawn for.
+    pub fn new(k0: u64, k1: u64, base: u64, block: u64) -> Self {
+        let mut state = SipStateC::<1, 3>::new(k0, k1);
+        state.write(&base.to_le_bytes());
+        state.write(&block.to_le_bytes());
+        RademacherSource {
+            state,
+            counter: 0,
+            bit_buf: 0,
+            bits_left: 0,
+        }
+    }
+
+    /// Emits the next decorrelated `u64` in the stream: each call runs the
+    /// standard SipHash finalization on the current state to read a word,
+    /// then re-keys the state with an incrementing counter before the next
+    /// call, so consecutive words aren't simple shifts of one another.
+    pub fn next_u64(&mut self) -> u64 {
+        let out = self.state.finish();
+        self.counter = self.counter.wrapping_add(1);
+        self.state.write(&self.counter.to_le_bytes());
+        out
+    }
+
+    /// Slices the next sign bit off the stream: `true` means `-1`. Draws a
+    /// fresh word via [`Self::next_u64`] only once every 64 calls, reusing
+    /// the cached word's remaining bits the rest of the time, since a full
+    /// SipHash finalization per bit would make building a block's `g`
+    /// vector an order of magnitude more expensive than it needs to be.
+    #[inline]
+    pub fn next_sign(&mut self) -> bool {
+        if self.bits_left == 0 {
+            self.bit_buf = self.next_u64();
+            self.bits_left = 64;
+        }
+        self.bits_left -= 1;
+        (self.bit_buf >> self.bits_left) & 1 != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_stream() {
+        let mut a = RademacherSource::new(1, 2, 42, 0);
+        let mut b = RademacherSource::new(1, 2, 42, 0);
+        for _ in 0..8 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn adjacent_blocks_decorrelate() {
+        let mut a = RademacherSource::new(1, 2, 42, 0);
+        let mut b = RademacherSource::new(1, 2, 42, 1);
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn consecutive_words_are_not_shifts_of_each_other() {
+        let mut s = RademacherSource::new(1, 2, 42, 0);
+        let w0 = s.next_u64();
+        let w1 = s.next_u64();
+        assert_ne!(w0, w1);
+        assert_ne!(w0 >> 1, w1);
+    }
+
+    #[test]
+    fn next_sign_reuses_one_word_for_64_bits() {
+        let mut signs = RademacherSource::new(1, 2, 42, 0);
+        let mut reference = RademacherSource::new(1, 2, 42, 0);
+        let word = reference.next_u64();
+
+        for i in 0..64 {
+            let expected = (word >> (63 - i)) & 1 != 0;
+            assert_eq!(signs.next_sign(), expected, "bit {i} should come from the cached word");
+        }
+
+        // The 65th call must refill from a fresh word, not keep slicing the
+        // first one.
+        let next_word = reference.next_u64();
+        assert_eq!(signs.next_sign(), (next_word >> 63) & 1 != 0);
+    }
+}
@@ -0,0 +1,690 @@
+use std::hash::{Hash, Hasher};
+
+use xxhash_rust::xxh3::Xxh3;
+
+/// A hasher that reduces an arbitrary [`Hash`] value down to a fixed-width
+/// feature hash, for use as the per-item hash in [`SimHash`](super::SimHash)
+/// and [`SuperBitSimHash`](super::SuperBitSimHash).
+pub trait SimHasher {
+    type T;
+
+    fn hash<U: Hash>(&self, item: &U) -> Self::T;
+}
+
+/// xxHash3, 64-bit output. Unkeyed; fast general-purpose default.
+pub struct Xxh3Hasher64;
+
+impl Xxh3Hasher64 {
+    pub fn new() -> Self {
+        Xxh3Hasher64
+    }
+}
+
+impl Default for Xxh3Hasher64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimHasher for Xxh3Hasher64 {
+    type T = u64;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u64 {
+        let mut h = Xxh3::new();
+        item.hash(&mut h);
+        h.finish()
+    }
+}
+
+/// xxHash3, 128-bit output.
+pub struct Xxh3Hasher128;
+
+impl Xxh3Hasher128 {
+    pub fn new() -> Self {
+        Xxh3Hasher128
+    }
+}
+
+impl Default for Xxh3Hasher128 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimHasher for Xxh3Hasher128 {
+    type T = u128;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u128 {
+        let mut h = Xxh3::new();
+        item.hash(&mut h);
+        h.digest128()
+    }
+}
+
+// --- SipHash, with a configurable number of compression/finalization rounds -
+
+#[derive(Clone)]
+pub(crate) struct SipStateC<const C: usize, const D: usize> {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: [u8; 8],
+    tail_len: usize,
+    len: u64,
+}
+
+impl<const C: usize, const D: usize> SipStateC<C, D> {
+    #[inline]
+    pub(crate) fn new(k0: u64, k1: u64) -> Self {
+        SipStateC {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: [0; 8],
+            tail_len: 0,
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn sipround(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline]
+    fn absorb_word(&mut self, m: u64) {
+        self.v3 ^= m;
+        for _ in 0..C {
+            self.sipround();
+        }
+        self.v0 ^= m;
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.len = self.len.wrapping_add(bytes.len() as u64);
+
+        if self.tail_len > 0 {
+            let need = 8 - self.tail_len;
+            let take = need.min(bytes.len());
+            self.tail[self.tail_len..self.tail_len + take].copy_from_slice(&bytes[..take]);
+            self.tail_len += take;
+            bytes = &bytes[take..];
+            if self.tail_len == 8 {
+                let m = u64::from_le_bytes(self.tail);
+                self.absorb_word(m);
+                self.tail_len = 0;
+            }
+        }
+
+        while bytes.len() >= 8 {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[..8]);
+            self.absorb_word(u64::from_le_bytes(word));
+            bytes = &bytes[8..];
+        }
+
+        if !bytes.is_empty() {
+            self.tail[..bytes.len()].copy_from_slice(bytes);
+            self.tail_len = bytes.len();
+        }
+    }
+
+    /// Runs the last-block absorption (tagged with the message length) and
+    /// `D` finalization rounds, returning the internal state words.
+    fn finalize(&self) -> (u64, u64, u64, u64) {
+        let mut s = self.clone();
+
+        let mut last = [0u8; 8];
+        last[..s.tail_len].copy_from_slice(&s.tail[..s.tail_len]);
+        last[7] = (s.len & 0xff) as u8;
+        let m = u64::from_le_bytes(last);
+
+        s.v3 ^= m;
+        for _ in 0..C {
+            s.sipround();
+        }
+        s.v0 ^= m;
+
+        s.v2 ^= 0xff;
+        for _ in 0..D {
+            s.sipround();
+        }
+
+        (s.v0, s.v1, s.v2, s.v3)
+    }
+
+    fn finish_64(&self) -> u64 {
+        let (v0, v1, v2, v3) = self.finalize();
+        v0 ^ v1 ^ v2 ^ v3
+    }
+
+    /// The 128-bit SipHash variant: after the first output is read, `v1` is
+    /// re-keyed and `D` more rounds produce the second 64-bit half.
+    fn finish_128(&self) -> u128 {
+        let (v0, v1, v2, v3) = self.finalize();
+        let low = v0 ^ v1 ^ v2 ^ v3;
+
+        let mut s = SipStateC::<C, D> {
+            v0,
+            v1: v1 ^ 0xdd,
+            v2,
+            v3,
+            tail: [0; 8],
+            tail_len: 0,
+            len: 0,
+        };
+        for _ in 0..D {
+            s.sipround();
+        }
+        let high = s.v0 ^ s.v1 ^ s.v2 ^ s.v3;
+
+        ((high as u128) << 64) | low as u128
+    }
+}
+
+impl<const C: usize, const D: usize> Hasher for SipStateC<C, D> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        SipStateC::write(self, bytes)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.finish_64()
+    }
+}
+
+/// Keyed SipHash with `C` compression rounds per 8-byte word and `D`
+/// finalization rounds, 64-bit output. `SimSipHasher64` (SipHash-2-4) is the
+/// default; `SimSipHasher64C<1, 3>` (SipHash-1-3) trades cryptographic
+/// strength for roughly 2x faster feature hashing, which is all SimHash
+/// needs when near-duplicate quality rather than collision-resistance is
+/// the goal.
+pub struct SimSipHasher64C<const C: usize, const D: usize> {
+    k0: u64,
+    k1: u64,
+}
+
+impl<const C: usize, const D: usize> SimSipHasher64C<C, D> {
+    pub fn new(k0: u64, k1: u64) -> Self {
+        SimSipHasher64C { k0, k1 }
+    }
+}
+
+impl<const C: usize, const D: usize> SimHasher for SimSipHasher64C<C, D> {
+    type T = u64;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u64 {
+        let mut h = SipStateC::<C, D>::new(self.k0, self.k1);
+        item.hash(&mut h);
+        h.finish_64()
+    }
+}
+
+/// Keyed SipHash-2-4, 64-bit output.
+pub type SimSipHasher64 = SimSipHasher64C<2, 4>;
+
+/// Keyed SipHash with `C` compression rounds per 8-byte word and `D`
+/// finalization rounds, 128-bit output.
+pub struct SimSipHasher128C<const C: usize, const D: usize> {
+    k0: u64,
+    k1: u64,
+}
+
+impl<const C: usize, const D: usize> SimSipHasher128C<C, D> {
+    pub fn new(k0: u64, k1: u64) -> Self {
+        SimSipHasher128C { k0, k1 }
+    }
+}
+
+impl<const C: usize, const D: usize> SimHasher for SimSipHasher128C<C, D> {
+    type T = u128;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u128 {
+        let mut h = SipStateC::<C, D>::new(self.k0, self.k1);
+        item.hash(&mut h);
+        h.finish_128()
+    }
+}
+
+/// Keyed SipHash-2-4, 128-bit output.
+pub type SimSipHasher128 = SimSipHasher128C<2, 4>;
+
+// --- AES-accelerated hasher --------------------------------------------------
+
+/// AES-round-based mixing: AES-NI on x86-64 when available at runtime, a
+/// multiply/rotate fallback everywhere else. `SimHash` only needs a good
+/// avalanche over the output bits, not cryptographic strength, so a single
+/// AES round per 16-byte block is enough to far outrun xxh3/SipHash on
+/// token-heavy workloads (document shingles, k-mers) while keeping
+/// signature quality unchanged.
+mod ahash_backend {
+    const FALLBACK_POLY: u128 = 0x243f_6a88_85a3_08d3_1319_8a2e_0370_7344;
+
+    #[inline]
+    fn fallback_round(state: u128, key: u128, block: u128) -> u128 {
+        let mixed = (state ^ block).wrapping_mul(FALLBACK_POLY) ^ key;
+        mixed.rotate_left(31) ^ mixed.rotate_right(17)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "aes")]
+    unsafe fn aesni_round(state: u128, key: u128, block: u128) -> u128 {
+        use core::arch::x86_64::{__m128i, _mm_aesenc_si128};
+        let s: __m128i = core::mem::transmute(state ^ block);
+        let k: __m128i = core::mem::transmute(key);
+        core::mem::transmute(_mm_aesenc_si128(s, k))
+    }
+
+    #[inline]
+    pub fn round(state: u128, key: u128, block: u128) -> u128 {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if std::is_x86_feature_detected!("aes") {
+                return unsafe { aesni_round(state, key, block) };
+            }
+        }
+        fallback_round(state, key, block)
+    }
+}
+
+struct AHashState {
+    state: u128,
+    key: u128,
+    pending: [u8; 16],
+    pending_len: usize,
+    len: u64,
+}
+
+impl AHashState {
+    fn new(key: u128) -> Self {
+        AHashState {
+            state: key,
+            key,
+            pending: [0; 16],
+            pending_len: 0,
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn absorb(&mut self, block: u128) {
+        self.state = ahash_backend::round(self.state, self.key, block);
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.len = self.len.wrapping_add(bytes.len() as u64);
+
+        if self.pending_len > 0 {
+            let need = 16 - self.pending_len;
+            let take = need.min(bytes.len());
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&bytes[..take]);
+            self.pending_len += take;
+            bytes = &bytes[take..];
+            if self.pending_len == 16 {
+                self.absorb(u128::from_le_bytes(self.pending));
+                self.pending_len = 0;
+            }
+        }
+
+        while bytes.len() >= 16 {
+            let mut block = [0u8; 16];
+            block.copy_from_slice(&bytes[..16]);
+            self.absorb(u128::from_le_bytes(block));
+            bytes = &bytes[16..];
+        }
+
+        if !bytes.is_empty() {
+            self.pending[..bytes.len()].copy_from_slice(bytes);
+            self.pending_len = bytes.len();
+        }
+    }
+
+    fn finalize(&self) -> u128 {
+        let mut state = self.state;
+        if self.pending_len > 0 {
+            let mut tail = [0u8; 16];
+            tail[..self.pending_len].copy_from_slice(&self.pending[..self.pending_len]);
+            state = ahash_backend::round(state, self.key, u128::from_le_bytes(tail));
+        }
+        let len_block = (self.len as u128) | ((self.len as u128) << 64);
+        ahash_backend::round(state, self.key, len_block)
+    }
+
+    fn finish_64(&self) -> u64 {
+        let s = self.finalize();
+        (s as u64) ^ ((s >> 64) as u64)
+    }
+
+    fn finish_128(&self) -> u128 {
+        self.finalize()
+    }
+}
+
+impl Hasher for AHashState {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        AHashState::write(self, bytes)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.finish_64()
+    }
+}
+
+#[inline]
+fn default_key() -> u128 {
+    0x243f_6a88_85a3_08d3_1319_8a2e_0370_7344
+}
+
+/// AES-accelerated `SimHasher`, 64-bit output.
+pub struct AHash64 {
+    key: u128,
+}
+
+impl AHash64 {
+    pub fn new() -> Self {
+        AHash64 { key: default_key() }
+    }
+
+    /// Keyed constructor so signatures are reproducible given the same keys.
+    pub fn with_keys(k0: u64, k1: u64) -> Self {
+        AHash64 {
+            key: ((k0 as u128) << 64) | k1 as u128,
+        }
+    }
+}
+
+impl Default for AHash64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimHasher for AHash64 {
+    type T = u64;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u64 {
+        let mut h = AHashState::new(self.key);
+        item.hash(&mut h);
+        h.finish_64()
+    }
+}
+
+/// AES-accelerated `SimHasher`, 128-bit output.
+pub struct AHash128 {
+    key: u128,
+}
+
+impl AHash128 {
+    pub fn new() -> Self {
+        AHash128 { key: default_key() }
+    }
+
+    /// Keyed constructor so signatures are reproducible given the same keys.
+    pub fn with_keys(k0: u64, k1: u64) -> Self {
+        AHash128 {
+            key: ((k0 as u128) << 64) | k1 as u128,
+        }
+    }
+}
+
+impl Default for AHash128 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimHasher for AHash128 {
+    type T = u128;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u128 {
+        let mut h = AHashState::new(self.key);
+        item.hash(&mut h);
+        h.finish_128()
+    }
+}
+
+// --- FxHash ------------------------------------------------------------------
+
+/// The constant from rustc's internal FxHash: a multiply-xor-rotate mix that
+/// has near-zero per-call overhead for short, fixed-width inputs (8/16-byte
+/// feature ids) where xxh3's streaming setup is overkill, at the cost of
+/// weaker avalanche than xxh3/SipHash/AHash for larger inputs.
+const FX_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+struct FxState {
+    hash: u64,
+    pending: [u8; 8],
+    pending_len: usize,
+}
+
+impl FxState {
+    #[inline]
+    fn new(seed: u64) -> Self {
+        FxState {
+            hash: seed,
+            pending: [0; 8],
+            pending_len: 0,
+        }
+    }
+
+    #[inline]
+    fn mix(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        if self.pending_len > 0 {
+            let need = 8 - self.pending_len;
+            let take = need.min(bytes.len());
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&bytes[..take]);
+            self.pending_len += take;
+            bytes = &bytes[take..];
+            if self.pending_len == 8 {
+                self.mix(u64::from_le_bytes(self.pending));
+                self.pending_len = 0;
+            }
+        }
+
+        while bytes.len() >= 8 {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[..8]);
+            self.mix(u64::from_le_bytes(word));
+            bytes = &bytes[8..];
+        }
+
+        if !bytes.is_empty() {
+            self.pending[..bytes.len()].copy_from_slice(bytes);
+            self.pending_len = bytes.len();
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        if self.pending_len > 0 {
+            let mut tail = self.clone_hash();
+            let mut word = [0u8; 8];
+            word[..self.pending_len].copy_from_slice(&self.pending[..self.pending_len]);
+            tail.mix(u64::from_le_bytes(word));
+            tail.hash
+        } else {
+            self.hash
+        }
+    }
+
+    #[inline]
+    fn clone_hash(&self) -> FxState {
+        FxState {
+            hash: self.hash,
+            pending: [0; 8],
+            pending_len: 0,
+        }
+    }
+}
+
+impl Hasher for FxState {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        FxState::write(self, bytes)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        FxState::finish(self)
+    }
+}
+
+/// FxHash, 64-bit output. Near-zero per-call overhead for short fixed-width
+/// keys (8/16-byte feature ids), with enough avalanche for SimHash's
+/// bit-level sign decisions.
+pub struct FxHash64;
+
+impl FxHash64 {
+    pub fn new() -> Self {
+        FxHash64
+    }
+}
+
+impl Default for FxHash64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimHasher for FxHash64 {
+    type T = u64;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u64 {
+        let mut h = FxState::new(0);
+        item.hash(&mut h);
+        h.finish()
+    }
+}
+
+/// FxHash, 128-bit output: two independently-keyed FxHash lanes run over the
+/// same input and are concatenated, so it plugs into
+/// `SimHash<FxHash128, u128, 128>` unchanged.
+pub struct FxHash128;
+
+impl FxHash128 {
+    pub fn new() -> Self {
+        FxHash128
+    }
+}
+
+impl Default for FxHash128 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimHasher for FxHash128 {
+    type T = u128;
+
+    #[inline]
+    fn hash<U: Hash>(&self, item: &U) -> u128 {
+        let mut lo = FxState::new(0);
+        let mut hi = FxState::new(0x9E37_79B9_7F4A_7C15);
+        item.hash(&mut lo);
+        item.hash(&mut hi);
+        ((hi.finish() as u128) << 64) | lo.finish() as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simhash::{SimHash, SimHashBits};
+
+    #[test]
+    fn ahash64_is_deterministic_given_same_keys() {
+        let h1 = AHash64::with_keys(1, 2);
+        let h2 = AHash64::with_keys(1, 2);
+        assert_eq!(h1.hash(&"hello world"), h2.hash(&"hello world"));
+    }
+
+    #[test]
+    fn ahash64_differs_across_keys() {
+        let h1 = AHash64::with_keys(1, 2);
+        let h2 = AHash64::with_keys(3, 4);
+        assert_ne!(h1.hash(&"hello world"), h2.hash(&"hello world"));
+    }
+
+    #[test]
+    fn ahash128_plugs_into_sim_hash() {
+        let sim_hash = SimHash::<AHash128, u128, 128>::new(AHash128::with_keys(1, 2));
+        let s1 = sim_hash.create_signature("the quick brown fox".split_whitespace());
+        let s2 = sim_hash.create_signature("the quick brown fox jumps".split_whitespace());
+        assert!(s1.hamming_distance(&s2) < 40);
+    }
+
+    #[test]
+    fn sip24_type_alias_matches_explicit_const_generic() {
+        // `SimSipHasher64` is just `SimSipHasher64C<2, 4>` under the hood.
+        let sip24 = SimSipHasher64::new(1, 2);
+        let sip24_explicit = SimSipHasher64C::<2, 4>::new(1, 2);
+        assert_eq!(sip24.hash(&"hello world"), sip24_explicit.hash(&"hello world"));
+    }
+
+    #[test]
+    fn sip13_is_keyed_and_deterministic() {
+        let sip13_a = SimSipHasher64C::<1, 3>::new(1, 2);
+        let sip13_b = SimSipHasher64C::<1, 3>::new(1, 2);
+        let sip13_c = SimSipHasher64C::<1, 3>::new(3, 4);
+        assert_eq!(sip13_a.hash(&"hello world"), sip13_b.hash(&"hello world"));
+        assert_ne!(sip13_a.hash(&"hello world"), sip13_c.hash(&"hello world"));
+    }
+
+    #[test]
+    fn sip13_plugs_into_sim_hash() {
+        let sim_hash = SimHash::<SimSipHasher64C<1, 3>, u64, 64>::new(SimSipHasher64C::new(1, 2));
+        let s1 = sim_hash.create_signature("the quick brown fox".split_whitespace());
+        let s2 = sim_hash.create_signature("the quick brown fox jumps".split_whitespace());
+        assert!(s1.hamming_distance(&s2) < 20);
+    }
+
+    #[test]
+    fn fxhash64_is_deterministic_for_short_keys() {
+        let h = FxHash64::new();
+        for i in 0u64..100 {
+            assert_eq!(h.hash(&i), h.hash(&i));
+        }
+    }
+
+    #[test]
+    fn fxhash64_distinguishes_adjacent_short_keys() {
+        let h = FxHash64::new();
+        assert_ne!(h.hash(&1u64), h.hash(&2u64));
+    }
+
+    #[test]
+    fn fxhash128_plugs_into_sim_hash() {
+        let sim_hash = SimHash::<FxHash128, u128, 128>::new(FxHash128::new());
+        let s1 = sim_hash.create_signature(0u64..1000);
+        let s2 = sim_hash.create_signature((0u64..1000).filter(|i| i % 7 != 0));
+        assert!(s1.hamming_distance(&s2) < 40);
+    }
+}
@@ -2,10 +2,83 @@
 use core::marker::PhantomData;
 use std::hash::Hash;
 use std::hash::Hasher;
+use super::rademacher::RademacherSource;
 use super::sim_hasher::SimHasher;
 use super::SimHashBits;
 use xxhash_rust::xxh3::Xxh3;
 
+/// SIMD lane width the per-row dot product is unrolled to; `q_blocks` rows
+/// are padded out to a multiple of this so the inner loop is branch-free.
+const LANES: usize = 8;
+
+#[inline]
+fn pad_to_lanes(n: usize) -> usize {
+    n.div_ceil(LANES) * LANES
+}
+
+/// Running Kahan-compensated sum, used by the portable (`new_portable`)
+/// construction path to keep float reduction order fixed across hosts.
+struct Kahan {
+    sum: f32,
+    c: f32,
+}
+
+impl Kahan {
+    #[inline]
+    fn new() -> Self {
+        Kahan { sum: 0.0, c: 0.0 }
+    }
+
+    #[inline]
+    fn add(&mut self, x: f32) {
+        let y = x - self.c;
+        let t = self.sum + y;
+        self.c = (t - self.sum) - y;
+        self.sum = t;
+    }
+
+    #[inline]
+    fn sum(&self) -> f32 {
+        self.sum
+    }
+}
+
+/// Version tag for the [`SuperBitSimHash::save_state`] byte blob.
+///
+/// Bumped to 2 when `rk0`/`rk1` were added to the header: a version-1 blob
+/// is shorter and lays its fields out differently, so loading it as if it
+/// were version 2 would silently misread old `q_blocks` bytes as key
+/// material instead of erroring. There's no compatibility shim for version
+/// 1 blobs; `load_state` rejects them via `StateError::UnsupportedVersion`.
+const STATE_VERSION: u32 = 2;
+
+/// Error returned by [`SuperBitSimHash::load_state`] when the byte blob
+/// isn't a valid, compatible state dump.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StateError {
+    /// The blob is shorter than the header, or its body length doesn't match
+    /// the size its own header claims.
+    Truncated,
+    /// The blob was written by an incompatible format version.
+    UnsupportedVersion(u32),
+    /// The blob's `r`/`m`/`L` don't agree with each other.
+    InvalidShape,
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::Truncated => write!(f, "superbit state blob is truncated"),
+            StateError::UnsupportedVersion(v) => {
+                write!(f, "superbit state blob has unsupported version {v}")
+            }
+            StateError::InvalidShape => write!(f, "superbit state blob has an invalid shape"),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
 pub struct SuperBitSimHash<H, S, const L: usize>
 where
     H: SimHasher<T = u64>,
@@ -13,9 +86,20 @@ where
 {
     hasher: H,
     r: usize,                 // superbit depth (block size)
+    r_pad: usize,              // r rounded up to a multiple of LANES
     m: usize,                 // number of blocks = L / r
-    q_blocks: Vec<Vec<f32>>,  // each is r×r row-major orthonormal
+    q_blocks: Vec<Vec<f32>>,  // each is r×r_pad row-major, padding columns zeroed
     seed: u64,
+    // "Portable" instances use libm + Kahan-compensated summation (both when
+    // realizing q_blocks and in the per-item dot product), trading speed for
+    // a reduction order that's fixed regardless of host architecture/
+    // compiler, so signatures can be persisted and compared across machines.
+    portable: bool,
+    // Keys for the per-(item, block) RademacherSource keystream. Default to
+    // a fixed, seed-derived pair so `new`/`new_portable` stay reproducible
+    // without extra configuration; override with `with_rademacher_keys`.
+    rk0: u64,
+    rk1: u64,
     _phantom: PhantomData<S>, // keep S "used" at the type level
 }
 
@@ -24,26 +108,69 @@ where
     H: SimHasher<T = u64>,
     S: SimHashBits,
 {
+    /// Fast, lane-vectorized construction. `create_signature_weighted`'s
+    /// per-row dot product accumulates `LANES` columns at a time, which lets
+    /// the compiler vectorize the reduction but means the result depends on
+    /// `LANES` and isn't guaranteed to match a signature computed by a
+    /// different crate version (or by [`Self::new_portable`], which uses a
+    /// sequential Kahan-compensated sum). Use `new_portable` instead when signatures
+    /// must be reproducible across releases or machines.
     pub fn new(hasher: H, r: usize, seed: u64) -> Self {
+        Self::build(hasher, r, seed, false)
+    }
+
+    /// Like [`Self::new`], but realizes `q_blocks` with `libm` transcendental
+    /// functions and Kahan-compensated summation, and uses the same
+    /// compensated summation in the per-item projection. The result is a
+    /// "stable-hash" mode: the same input yields a byte-identical signature
+    /// regardless of host architecture or compiler, so signatures produced
+    /// on one machine can be compared against ones computed independently
+    /// on another. [`Self::save_state`] persists the realized matrices.
+    pub fn new_portable(hasher: H, r: usize, seed: u64) -> Self {
+        Self::build(hasher, r, seed, true)
+    }
+
+    fn build(hasher: H, r: usize, seed: u64, portable: bool) -> Self {
         assert!(r > 0 && L % r == 0, "r must divide L");
         let m = L / r;
+        let r_pad = pad_to_lanes(r);
         let mut q_blocks = Vec::with_capacity(m);
         for b in 0..m {
-            q_blocks.push(Self::make_orthonormal_block(
-                r,
-                seed ^ ((b as u64) * 0x9E37_79B9),
-            ));
+            let block_seed = seed ^ ((b as u64) * 0x9E37_79B9);
+            let core = if portable {
+                Self::make_orthonormal_block_portable(r, block_seed)
+            } else {
+                Self::make_orthonormal_block(r, block_seed)
+            };
+            let mut padded = vec![0f32; r * r_pad];
+            for row in 0..r {
+                padded[row * r_pad..row * r_pad + r].copy_from_slice(&core[row * r..row * r + r]);
+            }
+            q_blocks.push(padded);
         }
         Self {
             hasher,
             r,
+            r_pad,
             m,
             q_blocks,
             seed,
+            portable,
+            rk0: seed,
+            rk1: seed ^ 0x9E37_79B9_7F4A_7C15,
             _phantom: PhantomData,
         }
     }
 
+    /// Overrides the keys used to seed the per-`(item, block)`
+    /// [`RademacherSource`] keystream, for reproducibility independent of
+    /// `seed` (which only drives the `q_blocks` matrices).
+    pub fn with_rademacher_keys(mut self, k0: u64, k1: u64) -> Self {
+        self.rk0 = k0;
+        self.rk1 = k1;
+        self
+    }
+
     // Classical (stable enough for small r) Gram–Schmidt on a random r×r
     fn make_orthonormal_block(r: usize, seed: u64) -> Vec<f32> {
         let mut mat = vec![0f32; r * r];
@@ -94,6 +221,46 @@ where
         ((v >> 11) as f32 + 0.5) * (1.0 / ((1u64 << 53) as f32))
     }
 
+    // Same construction as `make_orthonormal_block`, but with `libm`
+    // transcendentals in the Box-Muller draw and Kahan-compensated
+    // summation in the Gram-Schmidt reductions, so the realized
+    // matrix is byte-identical across host architectures/compilers.
+    fn make_orthonormal_block_portable(r: usize, seed: u64) -> Vec<f32> {
+        let mut mat = vec![0f32; r * r];
+        let mut k = 0u64;
+        for i in 0..r {
+            for j in 0..r {
+                let u1 = Self::u01(seed, k ^ ((i as u64) << 16) ^ (j as u64));
+                let u2 = Self::u01(seed, k.wrapping_mul(0x9E37_79B97F4A7C15) ^ 0xBF58_476D);
+                k = k.wrapping_add(1);
+                let r2 = libm::sqrtf(-2.0f32 * libm::logf(u1));
+                let th = 2.0f32 * std::f32::consts::PI * u2;
+                mat[i * r + j] = r2 * libm::cosf(th);
+            }
+        }
+        for j in 0..r {
+            for p in 0..j {
+                let mut dot = Kahan::new();
+                for i in 0..r {
+                    dot.add(mat[i * r + j] * mat[i * r + p]);
+                }
+                let dot = dot.sum();
+                for i in 0..r {
+                    mat[i * r + j] -= dot * mat[i * r + p];
+                }
+            }
+            let mut n = Kahan::new();
+            for i in 0..r {
+                n.add(mat[i * r + j] * mat[i * r + j]);
+            }
+            let n = libm::sqrtf(n.sum()).max(1e-12);
+            for i in 0..r {
+                mat[i * r + j] /= n;
+            }
+        }
+        mat
+    }
+
     /// Unweighted items: treat each item as weight 1.0 (like classic SimHash).
     pub fn create_signature<U>(&self, iter: impl Iterator<Item = U>) -> S
     where
@@ -109,8 +276,10 @@ where
         U: Hash,
     {
         let mut counts = vec![0f32; L];
-        // Reuse a single buffer for g to avoid per-block allocations.
-        let mut g = vec![0f32; self.r];
+        // Reuse a single buffer for the Rademacher sign mask to avoid
+        // per-block allocations. Padding lanes stay `false` (i.e. "+1"),
+        // which is harmless since the matching q_blocks columns are zero.
+        let mut neg = vec![false; self.r_pad];
 
         for (item, w) in iter {
             if w == 0.0 { continue; }
@@ -120,30 +289,62 @@ where
 
             // for each block, build g in {+1,-1}^r and accumulate Q_b * g
             for b in 0..self.m {
-                let qb = &self.q_blocks[b]; // row-major r×r
-
-                // Seed a tiny PRNG (SplitMix64) once per (item, block)
-                let mut s = self.seed
-                    ^ base
-                    ^ ((b as u64) << 32)
-                    ^ 0x9E37_79B9_7F4A_7C15;
-
-                // Fill g[j] ∈ {+1,-1} using SplitMix64
-                for j in 0..self.r {
-                    s = Self::splitmix64(s);
-                    g[j] = if (s >> 63) == 0 { 1.0 } else { -1.0 };
+                let qb = &self.q_blocks[b]; // row-major r×r_pad
+
+                // Seed a decorrelated sign keystream from the item's base
+                // hash and the block index, rather than a SplitMix64 whose
+                // seeds across adjacent blocks only differ in a constant.
+                let mut rademacher = RademacherSource::new(self.rk0, self.rk1, base, b as u64);
+
+                // g[j] ∈ {+1,-1}, recorded as a sign mask so the dot product
+                // below can use add/sub instead of a multiply.
+                for slot in neg.iter_mut().take(self.r) {
+                    *slot = rademacher.next_sign();
                 }
 
-                // u = Q_b * g (r dot-products, row-major is cache-friendly here)
+                // u = Q_b * g (r dot-products, row-major is cache-friendly here).
                 let off = b * self.r;
-                for row in 0..self.r {
-                    let mut acc = 0f32;
-                    let row_off = row * self.r;
-                    // dot(row, g)
-                    for col in 0..self.r {
-                        acc += qb[row_off + col] * g[col];
+                if self.portable {
+                    // Kahan-compensated, strictly sequential summation so the
+                    // reduction order (and therefore the result) is the same
+                    // on every host.
+                    for row in 0..self.r {
+                        let row_off = row * self.r_pad;
+                        let mut acc = Kahan::new();
+                        for col in 0..self.r_pad {
+                            let q = qb[row_off + col];
+                            acc.add(if neg[col] { -q } else { q });
+                        }
+                        counts[off + row] += w * acc.sum();
+                    }
+                } else {
+                    // LANES independent running accumulators per row, walked
+                    // LANES columns at a time: each chunk's additions are
+                    // data-parallel across lanes, which is what lets the
+                    // compiler emit real SIMD for this loop (the Kahan branch
+                    // above can't vectorize the same way, since every `add`
+                    // depends on the previous compensation term). This is a
+                    // deliberate reduction-order change from a plain
+                    // sequential sum, so non-portable signatures are *not*
+                    // guaranteed to match earlier releases bit-for-bit; see
+                    // the doc comment on `new` for the stability this
+                    // mode does (and doesn't) promise.
+                    for row in 0..self.r {
+                        let row_off = row * self.r_pad;
+                        let row_slice = &qb[row_off..row_off + self.r_pad];
+                        let mut lane_acc = [0f32; LANES];
+                        for (q_chunk, neg_chunk) in
+                            row_slice.chunks_exact(LANES).zip(neg.chunks_exact(LANES))
+                        {
+                            for ((acc, &q), &is_neg) in
+                                lane_acc.iter_mut().zip(q_chunk).zip(neg_chunk)
+                            {
+                                *acc += if is_neg { -q } else { q };
+                            }
+                        }
+                        let acc: f32 = lane_acc.iter().sum();
+                        counts[off + row] += w * acc;
                     }
-                    counts[off + row] += w * acc;
                 }
             }
         }
@@ -158,12 +359,97 @@ where
         out
     }
 
-    fn splitmix64(mut x: u64) -> u64 {
-        x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
-        let mut z = x;
-        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
-        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
-        z ^ (z >> 31)
+    /// Serializes `r`, `m`, `seed` and the realized `q_blocks` to a
+    /// versioned byte blob, so the exact matrices behind a `new_portable`
+    /// instance can be persisted and later reloaded with [`Self::load_state`]
+    /// on a different machine, without regenerating (and risking a
+    /// platform-dependent drift in) the orthonormal blocks.
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(53 + self.q_blocks.len() * self.r * self.r_pad * 4);
+        out.extend_from_slice(&STATE_VERSION.to_le_bytes());
+        out.extend_from_slice(&(self.r as u64).to_le_bytes());
+        out.extend_from_slice(&(self.r_pad as u64).to_le_bytes());
+        out.extend_from_slice(&(self.m as u64).to_le_bytes());
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.push(self.portable as u8);
+        out.extend_from_slice(&self.rk0.to_le_bytes());
+        out.extend_from_slice(&self.rk1.to_le_bytes());
+        for block in &self.q_blocks {
+            for v in block {
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// Reconstructs a [`SuperBitSimHash`] from a blob produced by
+    /// [`Self::save_state`], reusing the serialized `q_blocks` verbatim
+    /// instead of regenerating them from `seed`.
+    pub fn load_state(hasher: H, bytes: &[u8]) -> Result<Self, StateError> {
+        const HEADER_LEN: usize = 4 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+        if bytes.len() < HEADER_LEN {
+            return Err(StateError::Truncated);
+        }
+
+        let version = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
+        if version != STATE_VERSION {
+            return Err(StateError::UnsupportedVersion(version));
+        }
+
+        let r = u64::from_le_bytes(bytes[4..12].try_into().unwrap()) as usize;
+        let r_pad = u64::from_le_bytes(bytes[12..20].try_into().unwrap()) as usize;
+        let m = u64::from_le_bytes(bytes[20..28].try_into().unwrap()) as usize;
+        let seed = u64::from_le_bytes(bytes[28..36].try_into().unwrap());
+        let portable = bytes[36] != 0;
+        let rk0 = u64::from_le_bytes(bytes[37..45].try_into().unwrap());
+        let rk1 = u64::from_le_bytes(bytes[45..53].try_into().unwrap());
+
+        // `r`, `r_pad` and `m` come straight from the blob, so validate their
+        // relationship (and do every multiplication checked) before using
+        // them as allocation/slicing sizes: a corrupted blob could otherwise
+        // overflow-wrap `m * r` or `m * r * r_pad` and falsely pass the shape
+        // check below.
+        if r == 0 || m == 0 || r_pad != pad_to_lanes(r) {
+            return Err(StateError::InvalidShape);
+        }
+        let total_r = m.checked_mul(r).ok_or(StateError::InvalidShape)?;
+        if total_r != L {
+            return Err(StateError::InvalidShape);
+        }
+        let floats_len = m
+            .checked_mul(r)
+            .and_then(|v| v.checked_mul(r_pad))
+            .ok_or(StateError::InvalidShape)?;
+
+        let body = &bytes[HEADER_LEN..];
+        let body_len = floats_len
+            .checked_mul(4)
+            .ok_or(StateError::InvalidShape)?;
+        if body.len() != body_len {
+            return Err(StateError::Truncated);
+        }
+
+        let mut q_blocks = Vec::with_capacity(m);
+        for block_bytes in body.chunks_exact(r * r_pad * 4) {
+            let mut block = Vec::with_capacity(r * r_pad);
+            for word in block_bytes.chunks_exact(4) {
+                block.push(f32::from_le_bytes(word.try_into().unwrap()));
+            }
+            q_blocks.push(block);
+        }
+
+        Ok(Self {
+            hasher,
+            r,
+            r_pad,
+            m,
+            q_blocks,
+            seed,
+            portable,
+            rk0,
+            rk1,
+            _phantom: PhantomData,
+        })
     }
 }
 
@@ -220,4 +506,95 @@ mod tests {
         );
         assert!((low..=high).contains(&hd));
     }
+
+    #[test]
+    fn portable_state_round_trips() {
+        type Bits = BitArray<2>; // 2×64 = 128 bits
+        const L: usize = 128;
+        const R: usize = 16;
+
+        let sb = SuperBitSimHash::<Xxh3Hasher64, Bits, L>::new_portable(
+            Xxh3Hasher64::new(),
+            R,
+            0x00C0_FFEE,
+        );
+        let blob = sb.save_state();
+        let restored =
+            SuperBitSimHash::<Xxh3Hasher64, Bits, L>::load_state(Xxh3Hasher64::new(), &blob)
+                .expect("round-tripped state should load");
+
+        let items: Vec<(u64, f32)> = (0..1_000).map(|i| (i, 1.0)).collect();
+        let h1 = sb.create_signature_weighted(items.iter().copied());
+        let h2 = restored.create_signature_weighted(items.iter().copied());
+        assert_eq!(h1, h2);
+    }
+
+    /// Pins the non-portable path's reduction order: `LANES`-wide chunks,
+    /// each combined across chunks with one running accumulator per lane
+    /// (summed together only at the very end), not a plain column-by-column
+    /// sequential sum. This is the order `create_signature_weighted` must
+    /// keep producing for a given crate version; it intentionally differs
+    /// from `new_portable`'s sequential+Kahan order, which is the one with a
+    /// cross-version/cross-host stability guarantee.
+    #[test]
+    fn non_portable_matches_lane_grouped_reference() {
+        type Bits = BitArray<2>; // 2x64 = 128 bits
+        const L: usize = 128;
+        const R: usize = 16;
+
+        let sb = SuperBitSimHash::<Xxh3Hasher64, Bits, L>::new(Xxh3Hasher64::new(), R, 0x5EED);
+        let items: Vec<(u64, f32)> = (0..500).map(|i| (i, 1.0)).collect();
+
+        let mut counts = [0f32; L];
+        for &(item, w) in &items {
+            let base: u64 = sb.hasher.hash(&item);
+            for b in 0..sb.m {
+                let qb = &sb.q_blocks[b];
+                let mut rademacher = RademacherSource::new(sb.rk0, sb.rk1, base, b as u64);
+                let neg: Vec<bool> = (0..sb.r_pad).map(|_| rademacher.next_sign()).collect();
+
+                let off = b * sb.r;
+                for row in 0..sb.r {
+                    let row_off = row * sb.r_pad;
+                    let row_slice = &qb[row_off..row_off + sb.r_pad];
+                    let mut lane_acc = [0f32; LANES];
+                    for (q_chunk, neg_chunk) in
+                        row_slice.chunks_exact(LANES).zip(neg.chunks_exact(LANES))
+                    {
+                        for ((acc, &q), &is_neg) in lane_acc.iter_mut().zip(q_chunk).zip(neg_chunk)
+                        {
+                            *acc += if is_neg { -q } else { q };
+                        }
+                    }
+                    let acc: f32 = lane_acc.iter().sum();
+                    counts[off + row] += w * acc;
+                }
+            }
+        }
+        let mut expected = Bits::zero();
+        for (i, &c) in counts.iter().enumerate() {
+            if c > 0.0 {
+                expected |= Bits::one() << i;
+            }
+        }
+
+        let actual = sb.create_signature_weighted(items.iter().copied());
+        assert_eq!(
+            actual, expected,
+            "non-portable path must match the canonical lane-grouped reduction order"
+        );
+    }
+
+    #[test]
+    fn load_state_rejects_bad_version() {
+        type Bits = BitArray<2>;
+        const L: usize = 128;
+
+        let mut blob = vec![0u8; 64];
+        blob[0..4].copy_from_slice(&99u32.to_le_bytes());
+        match SuperBitSimHash::<Xxh3Hasher64, Bits, L>::load_state(Xxh3Hasher64::new(), &blob) {
+            Err(e) => assert_eq!(e, StateError::UnsupportedVersion(99)),
+            Ok(_) => panic!("expected an UnsupportedVersion error"),
+        }
+    }
 }
\ No newline at end of file